@@ -113,7 +113,7 @@ use std::collections::Bound;
 /// ```
 #[derive(Clone, Debug)]
 pub struct Sampler {
-    bins: BTreeMap<usize, (usize, usize)>,
+    bins: BTreeMap<usize, (usize, usize, usize)>,
     next_id: usize,
     end: usize,
 }
@@ -130,33 +130,94 @@ impl Sampler {
     pub fn from_bins<I>(iter: I, bin_width: usize) -> Self
     where
         I: IntoIterator<Item = (usize, usize)>,
+    {
+        Self::from_weighted_bins(iter.into_iter().map(move |(bin, count)| {
+            // the bucket *centers* on bin, so it captures everything within bin_width/2 on either
+            // side. in general, the average bin value should therefore just be the bin value. the
+            // exception is the very first bin, which only holds things in [0, bin_width/2), since
+            // everything above that would be rounded to the *next* bin. so, for things in the very
+            // first bin, the average value is really bin_width/4. to avoid fractions, we instead
+            // oversample by a factor of 4.
+            let avg_bin_value = if bin == 0 { bin_width } else { 4 * bin };
+            (bin, avg_bin_value, count)
+        }))
+    }
+
+    /// Create a new [`Sampler`] from buckets with explicit, possibly uneven, `[lower, upper)`
+    /// bounds.
+    ///
+    /// Each element is a `((lower, upper), count)` triple describing a half-open bucket and how
+    /// many values fell into it. This is handy when your data was summarized by a tool that emits
+    /// `[lo, hi) -> count` rows with varying widths (latencies, request sizes, transaction
+    /// values), rather than onto the uniform grid that [`Sampler::from_bins`] expects.
+    pub fn from_ranges<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = ((usize, usize), usize)>,
+    {
+        Self::from_weighted_bins(iter.into_iter().map(|((lower, upper), count)| {
+            // the representative value of a bucket is its midpoint, (lower + upper) / 2. to avoid
+            // fractions we scale every interval by a common factor of 2, so the weight is simply
+            // lower + upper.
+            ((lower + upper) / 2, lower + upper, count)
+        }))
+    }
+
+    /// Create a new [`Sampler`] from a logarithmically-bucketed histogram.
+    ///
+    /// Each input key is interpreted as a log-scale bucket index: bucket `k` covers
+    /// `[base^k, base^(k+1))`, and its representative value is the geometric midpoint
+    /// `base^k * sqrt(base)` (rounded to the nearest integer). The first bucket, `[0, base)`,
+    /// uses `base / 2` as its average to mirror the first-bin handling in [`Sampler::from_bins`].
+    ///
+    /// This is the shape emitted by HDR-style histograms, which pack a huge value range into a
+    /// handful of buckets with bounded relative error — ideal for heavy-tailed data like tail
+    /// latencies that would otherwise need thousands of linear bins.
+    pub fn from_log_bins<I>(iter: I, base: usize) -> Self
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        Self::from_weighted_bins(iter.into_iter().map(move |(k, count)| {
+            let avg = if k == 0 {
+                // the first bucket spans [0, base), so its average is base / 2.
+                base / 2
+            } else {
+                // the geometric midpoint of [base^k, base^(k+1)) is base^(k + 1/2).
+                (base as f64).powf(k as f64 + 0.5).round() as usize
+            };
+            (avg, avg, count)
+        }))
+    }
+
+    /// Build a [`Sampler`] from `(bin_value, interval_weight, count)` triples.
+    ///
+    /// `bin_value` is only recorded for reporting (see [`Sampler::errors`]); sampling depends on
+    /// `interval_weight` and `count` alone.
+    ///
+    /// We want the likelihood of selecting an id in a bin to be proportional to its average value
+    /// times `count`. The way to think about that in the context of sampling from a histogram is
+    /// that there are `count` ranges, each spanning an interval of the average bin value. We can
+    /// improve on this slightly by just keeping track of a single interval of width
+    /// `interval_weight * count`, and then convert the chosen value into an id by doing a `% count`.
+    fn from_weighted_bins<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, usize, usize)>,
     {
         let mut start = 0;
         let mut next_id = 0;
         let mut bins = BTreeMap::default();
 
-        for (bin, count) in iter {
+        for (bin_value, weight, count) in iter {
             if count == 0 {
                 continue;
             }
 
-            // we want the likelihood of selecting an id in this bin to be proportional to
-            // average bin value * `count`. the way to think about that in the context of sampling
-            // from a histogram is that there are `count` ranges, each spanning an interval of
-            // width `bin`. we can improve on this slightly by just keeping track of a single
-            // interval of width average bin value * count, and then convert the chosen value into
-            // an id by doing a % count.
-            bins.insert(start, (next_id, count));
+            bins.insert(start, (next_id, count, bin_value));
 
-            // the bucket *centers* on bin, so it captures everything within bin_width/2 on either
-            // side. in general, the average bin value should therefore just be the bin value. the
-            // exception is the very first bin, which only holds things in [0, bin_width/2), since
-            // everything above that would be rounded to the *next* bin. so, for things in the very
-            // first bin, the average value is really bin_width/4. to avoid fractions, we instead
-            // oversample by a factor of 4.
-            let avg_bin_value = if bin == 0 { bin_width } else { 4 * bin };
-
-            start += count * avg_bin_value;
+            // every bin must advance `start` by at least `count`, otherwise a zero weight (e.g. a
+            // centroid that rounded down to 0) would leave `start` unchanged, overwriting the
+            // previous bin's key and — if it happens for every bin — leaving `end == 0`, which
+            // makes `ind_sample` panic in `gen_range(0, 0)`.
+            start += count * weight.max(1);
             next_id += count;
         }
 
@@ -166,6 +227,195 @@ impl Sampler {
             end: start,
         }
     }
+
+    /// Report the multinomial variance of the *count* of values that fall in `bin`.
+    ///
+    /// Treating each sample as a draw from a multinomial distribution, the count `n` in a bin has
+    /// variance `n * (1 - n / total)`, where `total` is [`nvalues`](Sampler::nvalues). To turn this
+    /// into the variance of the bin's *proportion*, divide by `total * total`; [`stderr`] does the
+    /// equivalent for the standard error. Bins with a high relative variance — typically the small,
+    /// high-value tail bins — are the ones whose proportion you should trust least.
+    ///
+    /// [`stderr`]: Sampler::stderr
+    pub fn variance(&self, bin: usize) -> f64 {
+        let total = self.next_id as f64;
+        let n = self.count(bin) as f64;
+        n * (1.0 - n / total)
+    }
+
+    /// Report the standard error of the proportion of values that fall in `bin`.
+    ///
+    /// This is `sqrt(variance(bin)) / total`, expressed on the same `[0, 1]` scale as the bin's
+    /// proportion so the two can be compared directly.
+    pub fn stderr(&self, bin: usize) -> f64 {
+        self.variance(bin).sqrt() / self.next_id as f64
+    }
+
+    /// Iterate over `(bin_value, proportion, stderr)` for every bin, in ascending bin order.
+    ///
+    /// This lets callers see which bins are statistically reliable after a given number of
+    /// samples, and decide how long to run the generator (the distribution only settles after a
+    /// large number of draws).
+    pub fn errors<'a>(&'a self) -> impl Iterator<Item = (usize, f64, f64)> + 'a {
+        let total = self.next_id as f64;
+        let mut rows: Vec<_> = self.bins
+            .values()
+            .map(move |&(_, count, bin_value)| {
+                let n = count as f64;
+                let proportion = n / total;
+                let stderr = (n * (1.0 - n / total)).sqrt() / total;
+                (bin_value, proportion, stderr)
+            })
+            .collect();
+        // bins are stored in the order they were given, so sort them by value.
+        rows.sort_by_key(|&(bin_value, _, _)| bin_value);
+        rows.into_iter()
+    }
+
+    /// Render the stored distribution as a one-line Unicode sparkline.
+    ///
+    /// Each bin contributes a single block glyph in ascending bin order, picked from the eight
+    /// levels `▁▂▃▄▅▆▇█`. Each bin's proportion, relative to the tallest bin, is mapped linearly
+    /// onto the eight levels from zero upwards: the tallest bin reads as `█`, and a bin reads as
+    /// `▁` only if it holds roughly an eighth or less of the tallest bin's count (the smallest bin
+    /// is not stretched down to `▁`). This gives a dependency-free sanity check that the
+    /// distribution you fed in looks the way you expect.
+    pub fn sparkline(&self) -> String {
+        const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+        // bins are stored in the order they were given, so sort them by value.
+        let mut counts: Vec<_> = self.bins
+            .values()
+            .map(|&(_, count, bin_value)| (bin_value, count))
+            .collect();
+        counts.sort_by_key(|&(bin_value, _)| bin_value);
+
+        let max_count = counts.iter().map(|&(_, count)| count).max().unwrap_or(0);
+        if max_count == 0 {
+            return String::new();
+        }
+
+        counts
+            .into_iter()
+            .map(|(_, count)| {
+                // scale by 7.999 rather than 8 so the tallest bin lands on the top glyph (index 7)
+                // instead of overflowing, and no bin is promoted a level too high by rounding.
+                let level = (count as f64 / max_count as f64 * 7.999) as usize;
+                BLOCKS[level]
+            })
+            .collect()
+    }
+
+    /// Look up the recorded count for the bin whose value is `bin`, or 0 if there is none.
+    fn count(&self, bin: usize) -> usize {
+        self.bins
+            .values()
+            .find(|&&(_, _, bin_value)| bin_value == bin)
+            .map(|&(_, count, _)| count)
+            .unwrap_or(0)
+    }
+}
+
+/// Build a [`Sampler`] from a stream of raw observations in a single pass.
+///
+/// Rather than pre-aggregating your data into `(bin, count)` pairs, you can feed raw values in one
+/// at a time and let the builder maintain an approximate histogram with at most `B` bins. It uses
+/// [Ben-Haim & Tom-Tov's streaming histogram][paper]: a sorted list of `(centroid, count)` pairs
+/// where, whenever the list grows past `B` entries, the two adjacent pairs with the smallest
+/// centroid gap are merged. Because [`merge`](StreamingBuilder::merge) combines two builders the
+/// same way, histograms can be built in parallel over shards of a stream and then folded together.
+///
+/// ```
+/// # extern crate histogram_sampler;
+/// # use histogram_sampler::StreamingBuilder;
+/// let mut builder = StreamingBuilder::new(64);
+/// for v in &[1.0, 1.0, 2.0, 9.0, 9.0, 9.0] {
+///     builder.insert(*v);
+/// }
+/// let sampler = builder.into_sampler();
+/// # let _ = sampler;
+/// ```
+///
+/// [paper]: http://www.jmlr.org/papers/volume11/ben-haim10a/ben-haim10a.pdf
+#[derive(Clone, Debug)]
+pub struct StreamingBuilder {
+    max_bins: usize,
+    bins: Vec<(f64, usize)>,
+}
+
+impl StreamingBuilder {
+    /// Create a builder that keeps at most `max_bins` `(centroid, count)` pairs.
+    pub fn new(max_bins: usize) -> Self {
+        assert!(max_bins >= 1, "a histogram needs at least one bin");
+        StreamingBuilder {
+            max_bins,
+            bins: Vec::with_capacity(max_bins + 1),
+        }
+    }
+
+    /// Record a single observation.
+    pub fn insert(&mut self, v: f64) {
+        match self.bins
+            .binary_search_by(|&(c, _)| c.partial_cmp(&v).expect("observations must be finite"))
+        {
+            // we already have a centroid at exactly this value, so just bump its count.
+            Ok(i) => self.bins[i].1 += 1,
+            Err(i) => self.bins.insert(i, (v, 1)),
+        }
+        self.shrink();
+    }
+
+    /// Fold another builder into this one, enabling parallel or distributed construction.
+    ///
+    /// The two pair lists are concatenated and then repeatedly merged until `self`'s bin limit is
+    /// satisfied again. Merges that either builder already made are kept, so the result can differ
+    /// from that of a single builder fed all the same observations.
+    pub fn merge(mut self, other: StreamingBuilder) -> Self {
+        self.bins.extend(other.bins);
+        self.bins
+            .sort_by(|a, b| a.0.partial_cmp(&b.0).expect("observations must be finite"));
+        self.shrink();
+        self
+    }
+
+    /// Turn the accumulated histogram into a [`Sampler`].
+    ///
+    /// Each final centroid becomes a bin value (rounded to the nearest integer) and its count
+    /// becomes the bin count, which are then fed through the usual interval-building logic.
+    pub fn into_sampler(self) -> Sampler {
+        Sampler::from_weighted_bins(
+            self.bins
+                .into_iter()
+                .map(|(centroid, count)| {
+                    let bin_value = centroid.round() as usize;
+                    (bin_value, bin_value, count)
+                }),
+        )
+    }
+
+    /// Merge the closest adjacent pairs until at most `max_bins` remain.
+    fn shrink(&mut self) {
+        while self.bins.len() > self.max_bins {
+            // find the adjacent pair with the smallest centroid gap.
+            let mut closest = 0;
+            let mut min_gap = f64::INFINITY;
+            for i in 0..(self.bins.len() - 1) {
+                let gap = self.bins[i + 1].0 - self.bins[i].0;
+                if gap < min_gap {
+                    min_gap = gap;
+                    closest = i;
+                }
+            }
+
+            // merge them into their count-weighted mean.
+            let (c1, k1) = self.bins[closest];
+            let (c2, k2) = self.bins[closest + 1];
+            let k = k1 + k2;
+            let c = (c1 * k1 as f64 + c2 * k2 as f64) / k as f64;
+            self.bins[closest] = (c, k);
+            self.bins.remove(closest + 1);
+        }
+    }
 }
 
 impl rand::distributions::Sample<usize> for Sampler {
@@ -180,7 +430,7 @@ impl rand::distributions::IndependentSample<usize> for Sampler {
         let sample = rng.gen_range(0, self.end);
 
         // find the bin we're sampling from
-        let &(first_id, n) = self.bins
+        let &(first_id, n, _) = self.bins
             .range((Bound::Unbounded, Bound::Included(sample)))
             .next_back()
             .unwrap()
@@ -198,6 +448,91 @@ mod tests {
     use std::collections::HashMap;
     use rand::distributions::IndependentSample;
 
+    #[test]
+    fn from_ranges_uses_midpoints() {
+        let sampler = Sampler::from_ranges(vec![((0, 10), 4), ((10, 30), 2)]);
+        assert_eq!(sampler.nvalues(), 6);
+
+        // errors() reports the bucket midpoints in ascending order
+        let bins: Vec<_> = sampler.errors().map(|(bin, _, _)| bin).collect();
+        assert_eq!(bins, vec![5, 20]);
+    }
+
+    #[test]
+    fn from_log_bins_uses_geometric_midpoints() {
+        let sampler = Sampler::from_log_bins(vec![(0, 3), (1, 2), (2, 1)], 10);
+        assert_eq!(sampler.nvalues(), 6);
+
+        // [0, 10) -> base/2 = 5; [10, 100) -> 10*sqrt(10) ~= 32; [100, 1000) -> 100*sqrt(10) ~= 316
+        let bins: Vec<_> = sampler.errors().map(|(bin, _, _)| bin).collect();
+        assert_eq!(bins, vec![5, 32, 316]);
+    }
+
+    #[test]
+    fn streaming_builder_small_values_do_not_panic() {
+        // regression: sub-0.5 centroids round to 0, which used to leave `end == 0` and panic in
+        // `ind_sample`'s `gen_range(0, 0)`.
+        let mut builder = StreamingBuilder::new(8);
+        for _ in 0..10 {
+            builder.insert(0.3);
+        }
+
+        let sampler = builder.into_sampler();
+        assert_eq!(sampler.nvalues(), 10);
+
+        let mut rng = rand::thread_rng();
+        let id = sampler.ind_sample(&mut rng);
+        assert!(id < 10);
+    }
+
+    #[test]
+    fn streaming_builder_merge_collapses_to_bin_limit() {
+        let mut a = StreamingBuilder::new(2);
+        a.insert(1.0);
+        a.insert(3.0);
+
+        let mut b = StreamingBuilder::new(2);
+        b.insert(100.0);
+        b.insert(104.0);
+
+        // concatenating the two lists gives four pairs, which are merged down to two: the close
+        // neighbours (1, 3) and (100, 104) collapse to their count-weighted means.
+        let sampler = a.merge(b).into_sampler();
+        assert_eq!(sampler.nvalues(), 4);
+
+        let bins: Vec<_> = sampler.errors().map(|(bin, _, _)| bin).collect();
+        assert_eq!(bins, vec![2, 102]);
+    }
+
+    #[test]
+    fn variance_stderr_and_errors() {
+        let sampler = Sampler::from_bins(vec![(0, 3), (10, 1)], 10);
+        assert_eq!(sampler.nvalues(), 4);
+
+        // count variance for the bin at value 10: n = 1, total = 4 -> 1 * (1 - 1/4) = 0.75
+        assert!((sampler.variance(10) - 0.75).abs() < 1e-9);
+        assert!((sampler.stderr(10) - (0.75f64).sqrt() / 4.0).abs() < 1e-9);
+
+        // bins that were never recorded report zero variance
+        assert_eq!(sampler.variance(999), 0.0);
+
+        // errors() yields one entry per bin, with proportions summing to 1
+        let props: Vec<_> = sampler.errors().map(|(_, p, _)| p).collect();
+        assert_eq!(props.len(), 2);
+        assert!((props.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sparkline_maps_tallest_to_top_glyph() {
+        let sampler = Sampler::from_bins(vec![(0, 1), (10, 8)], 10);
+        let chars: Vec<char> = sampler.sparkline().chars().collect();
+
+        // one glyph per bin, tallest bin on the top block, and the shorter bin strictly lower
+        assert_eq!(chars.len(), 2);
+        assert_eq!(chars[1], '█');
+        assert!(chars[0] < chars[1]);
+    }
+
     #[test]
     fn it_works() {
         let stories_per_votecount = vec![